@@ -0,0 +1,41 @@
+//! API routes migrated off the Node/Next.js server (Phase B).
+//!
+//! Each route previously lived under `app/api/**/route.ts` in the Next.js
+//! project; they're ported here one at a time and registered in
+//! [`handle`]. Unmigrated paths fall through to a 404 so the gap is visible
+//! during the migration rather than silently serving nothing.
+
+use std::borrow::Cow;
+
+use tauri::http::{header, Method, Request, Response, StatusCode};
+use tauri::AppHandle;
+
+pub async fn handle(
+    _app: &AppHandle,
+    path: &str,
+    request: &Request<Vec<u8>>,
+) -> Response<Cow<'static, [u8]>> {
+    match (request.method(), path) {
+        (&Method::GET, "health") => health(),
+        _ => not_found(),
+    }
+}
+
+/// Liveness check used by the startup readiness probe
+/// (`protocol::probe`, run from `supervisor.rs`) to confirm the embedded
+/// protocol handler is serving.
+fn health() -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Cow::Borrowed(br#"{"status":"ok"}"#.as_slice()))
+        .unwrap()
+}
+
+fn not_found() -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Cow::Borrowed(br#"{"error":"not found"}"#.as_slice()))
+        .unwrap()
+}
@@ -0,0 +1,196 @@
+//! Loopback HTTP control server for external window/session automation.
+//!
+//! Distinct from the `handai://` app protocol (`protocol.rs`), this binds a
+//! small hand-rolled HTTP server (std only, same spirit as the original
+//! `wait_for_server` TCP probe) to an OS-assigned `127.0.0.1` port so a
+//! cooperating local process — kiosk, proctoring, or session-automation
+//! tooling — can show/hide/focus the Handai window without that logic
+//! living in the web layer. A random bearer token, written to the app data
+//! dir alongside the chosen port, gates every request so only processes
+//! that can already read local disk can drive the window.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+
+use tauri::{AppHandle, Manager};
+
+/// Starts the control server on a background thread and writes its port +
+/// bearer token to `control.json` in the app data dir. Call from `setup`.
+pub fn spawn(app: AppHandle) {
+    let data_dir = match app.path().app_data_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("[handai] control server disabled: app data dir unavailable: {e}");
+            return;
+        }
+    };
+    if let Err(e) = std::fs::create_dir_all(&data_dir) {
+        eprintln!("[handai] control server disabled: {e}");
+        return;
+    }
+
+    let listener = match TcpListener::bind("127.0.0.1:0") {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("[handai] control server failed to bind: {e}");
+            return;
+        }
+    };
+    let port = listener.local_addr().map(|a| a.port()).unwrap_or(0);
+    let token = generate_token();
+
+    let manifest_path = data_dir.join("control.json");
+    let manifest = format!(r#"{{"port":{port},"token":"{token}"}}"#);
+    if let Err(e) = std::fs::write(&manifest_path, manifest) {
+        eprintln!("[handai] control server failed to write control.json: {e}");
+        return;
+    }
+    // The token gates full window control; the default umask would
+    // otherwise leave this world-readable on most Linux/macOS setups.
+    if let Err(e) = restrict_to_owner(&manifest_path) {
+        eprintln!("[handai] control server failed to restrict control.json permissions: {e}");
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let app = app.clone();
+            let token = token.clone();
+            std::thread::spawn(move || handle_connection(stream, &app, &token));
+        }
+    });
+}
+
+fn handle_connection(mut stream: TcpStream, app: &AppHandle, token: &str) {
+    let mut reader = BufReader::new(stream.try_clone().expect("clone tcp stream"));
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+        return;
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("").to_string();
+
+    let mut authorized = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Authorization: ") {
+            authorized = value.trim() == format!("Bearer {token}");
+        }
+    }
+
+    if !authorized {
+        respond(&mut stream, 401, r#"{"error":"unauthorized"}"#);
+        return;
+    }
+
+    let window = app.get_webview_window("main");
+    let result = match (method.as_str(), path.as_str()) {
+        ("POST", "/window/show") => window.map(|w| w.show()),
+        ("POST", "/window/hide") => window.map(|w| w.hide()),
+        ("POST", "/window/focus") => window.map(|w| w.set_focus()),
+        ("POST", "/window/minimize") => window.map(|w| w.minimize()),
+        ("GET", "/window/state") => {
+            let body = window
+                .map(|w| {
+                    format!(
+                        r#"{{"visible":{},"focused":{},"minimized":{}}}"#,
+                        w.is_visible().unwrap_or(false),
+                        w.is_focused().unwrap_or(false),
+                        w.is_minimized().unwrap_or(false)
+                    )
+                })
+                .unwrap_or_else(|| r#"{"error":"window not found"}"#.into());
+            respond(&mut stream, 200, &body);
+            return;
+        }
+        _ => {
+            respond(&mut stream, 404, r#"{"error":"not found"}"#);
+            return;
+        }
+    };
+
+    match result {
+        Some(Ok(())) => respond(&mut stream, 200, r#"{"ok":true}"#),
+        Some(Err(e)) => respond(&mut stream, 500, &format!(r#"{{"error":"{e}"}}"#)),
+        None => respond(&mut stream, 404, r#"{"error":"window not found"}"#),
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: u16, body: &str) {
+    let reason = match status {
+        200 => "OK",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
+
+/// Generates a 32-byte token from the OS CSPRNG, hex-encoded. PID and
+/// wall-clock time are both readable by any other local process (e.g. via
+/// `/proc/<pid>/stat`), so neither is fit to derive a credential from —
+/// this needs to be genuinely unguessable on its own.
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).expect("OS CSPRNG unavailable");
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(unix)]
+fn restrict_to_owner(path: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+}
+
+/// No equivalent of owner-only POSIX permissions via std on Windows; the
+/// app data dir is already scoped to the current user's profile there.
+#[cfg(not(unix))]
+fn restrict_to_owner(_path: &std::path::Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_32_random_bytes_hex_encoded() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn tokens_are_not_reused() {
+        assert_ne!(generate_token(), generate_token());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn restrict_to_owner_sets_owner_only_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut path = std::env::temp_dir();
+        path.push(format!("handai-control-test-{}", generate_token()));
+        std::fs::write(&path, b"{}").unwrap();
+
+        restrict_to_owner(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,163 @@
+//! Native file export/import commands.
+//!
+//! WKWebView (macOS) does not support the HTML `download` attribute, so the
+//! web layer detects Tauri and calls these instead of driving a browser
+//! download. `export_file` replaces the old CSV-only `save_file`: the
+//! format picks the dialog filter/extension, and the source picks how
+//! `content` reaches disk. [`ExportSource::Inline`] still has to fully
+//! materialize `content` as one JSON string over the IPC bridge before the
+//! (now off-main-thread) write starts — fine for short CSV rows or JSON
+//! blobs the web layer already holds in memory. For anything large, the
+//! caller should write to a temp file itself and pass
+//! [`ExportSource::TempFile`], which only ever moves/copies bytes on disk
+//! and never holds the whole export in process memory at once.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri_plugin_dialog::{DialogExt, FilePath};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Xlsx,
+    Text,
+}
+
+impl ExportFormat {
+    fn filter_name(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV Files",
+            ExportFormat::Json => "JSON Files",
+            ExportFormat::Xlsx => "Excel Files",
+            ExportFormat::Text => "Text Files",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Text => "txt",
+        }
+    }
+
+    /// XLSX content arrives as base64 (it's zip bytes); everything else is
+    /// written out as plain UTF-8.
+    fn decode(self, content: String) -> Result<Vec<u8>, String> {
+        match self {
+            ExportFormat::Xlsx => base64::engine::general_purpose::STANDARD
+                .decode(content)
+                .map_err(|e| format!("invalid base64 content: {e}")),
+            ExportFormat::Csv | ExportFormat::Json | ExportFormat::Text => {
+                Ok(content.into_bytes())
+            }
+        }
+    }
+}
+
+fn with_extension(filename: &str, extension: &str) -> String {
+    if filename.ends_with(&format!(".{extension}")) {
+        filename.to_string()
+    } else {
+        format!("{filename}.{extension}")
+    }
+}
+
+/// Where `export_file` reads its bytes from.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ExportSource {
+    /// Content already in memory on the web side, decoded per `format`
+    /// (base64 for XLSX, UTF-8 otherwise).
+    Inline { content: String },
+    /// A file the caller already wrote to disk (e.g. a streamed XLSX
+    /// writer) — copied to the chosen path byte-for-byte, with no format
+    /// decoding and without ever holding the whole export in memory.
+    TempFile { temp_file_path: String },
+}
+
+/// Shows a native save-file dialog for `format` and writes `source` to the
+/// chosen path. Returns `true` if saved, `false` if the user cancelled.
+#[tauri::command]
+pub async fn export_file(
+    app: tauri::AppHandle,
+    filename: String,
+    format: ExportFormat,
+    source: ExportSource,
+) -> Result<bool, String> {
+    let path = app
+        .dialog()
+        .file()
+        .set_file_name(with_extension(&filename, format.extension()))
+        .add_filter(format.filter_name(), &[format.extension()])
+        .blocking_save_file();
+
+    let path = match path {
+        Some(FilePath::Path(p)) => p,
+        Some(_) => return Err("Unsupported path type".into()),
+        None => return Ok(false), // user cancelled
+    };
+
+    match source {
+        ExportSource::Inline { content } => {
+            let bytes = format.decode(content)?;
+            tauri::async_runtime::spawn_blocking(move || std::fs::write(&path, bytes))
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| e.to_string())?;
+        }
+        ExportSource::TempFile { temp_file_path } => {
+            let temp_path = std::path::PathBuf::from(temp_file_path);
+            tauri::async_runtime::spawn_blocking(move || {
+                std::fs::copy(&temp_path, &path)?;
+                std::fs::remove_file(&temp_path)
+            })
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(true)
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenedFile {
+    filename: String,
+    /// Base64-encoded so binary and text content round-trip through JSON
+    /// the same way.
+    content_base64: String,
+}
+
+/// Shows a native open-file dialog and reads the chosen file back to the
+/// web layer so it can round-trip import/export. Returns `None` if the
+/// user cancelled.
+#[tauri::command]
+pub async fn open_file(app: tauri::AppHandle) -> Result<Option<OpenedFile>, String> {
+    let path = app.dialog().file().blocking_pick_file();
+    let path = match path {
+        Some(FilePath::Path(p)) => p,
+        Some(_) => return Err("Unsupported path type".into()),
+        None => return Ok(None),
+    };
+
+    let filename = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let bytes = {
+        let path = path.clone();
+        tauri::async_runtime::spawn_blocking(move || std::fs::read(&path))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?
+    };
+
+    Ok(Some(OpenedFile {
+        filename,
+        content_base64: base64::engine::general_purpose::STANDARD.encode(bytes),
+    }))
+}
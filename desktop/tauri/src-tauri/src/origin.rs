@@ -0,0 +1,65 @@
+//! Origin allowlist enforced around the invoke pipeline.
+//!
+//! Commands like `export_file` are privileged — they write arbitrary content
+//! to a user-chosen path. Registering `handai://` as the app's protocol
+//! doesn't by itself stop a WebView that later navigates elsewhere (or an
+//! injected remote frame) from still reaching the IPC bridge, so every
+//! invocation is checked against the app's own origin before it reaches a
+//! command handler.
+
+use tauri::{Invoke, Runtime, Url};
+
+/// Origin the WebView navigates to in production, and in dev once a page
+/// has loaded through the embedded protocol.
+#[cfg(not(debug_assertions))]
+const ALLOWED_ORIGINS: &[&str] = &["handai://localhost"];
+
+/// In debug builds, also trust the Next.js dev server origin in case
+/// `devUrl` in tauri.conf.json still points `tauri dev` at `next dev` for
+/// hot reload.
+#[cfg(debug_assertions)]
+const ALLOWED_ORIGINS: &[&str] = &["handai://localhost", "http://localhost:3000"];
+
+/// Checks the origin an invoke came from against [`ALLOWED_ORIGINS`],
+/// rejecting anything we can't confirm (e.g. a webview URL we failed to
+/// read) rather than defaulting to allow.
+pub fn is_allowed_invoke<R: Runtime>(invoke: &Invoke<R>) -> bool {
+    invoke
+        .message
+        .webview()
+        .url()
+        .map(|url| is_allowed(&url))
+        .unwrap_or(false)
+}
+
+fn is_allowed(url: &Url) -> bool {
+    let host = url.host_str().unwrap_or("");
+    let origin = match url.port() {
+        Some(port) => format!("{}://{host}:{port}", url.scheme()),
+        None => format!("{}://{host}", url.scheme()),
+    };
+    ALLOWED_ORIGINS.contains(&origin.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_the_app_origin() {
+        let url = Url::parse("handai://localhost/dashboard").unwrap();
+        assert!(is_allowed(&url));
+    }
+
+    #[test]
+    fn rejects_an_unrelated_remote_origin() {
+        let url = Url::parse("https://evil.example/handai://localhost").unwrap();
+        assert!(!is_allowed(&url));
+    }
+
+    #[test]
+    fn rejects_the_same_host_on_an_unexpected_port() {
+        let url = Url::parse("http://localhost:9999/").unwrap();
+        assert!(!is_allowed(&url));
+    }
+}
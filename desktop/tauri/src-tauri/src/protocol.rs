@@ -0,0 +1,315 @@
+//! Embedded `handai://` custom URI scheme protocol (Phase B).
+//!
+//! Replaces the Node sidecar (see main.rs docs) with an in-process async
+//! protocol handler: requests under `/api/` are dispatched to the migrated
+//! routes in [`crate::api`], everything else is served from the bundled
+//! Next.js static export. The handler resolves on the Tauri async runtime
+//! rather than inline, since WKWebView/WebView2 will deadlock if a protocol
+//! callback blocks the main thread while a command also needs it.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, UriSchemeResponder};
+
+/// Scheme registered on the Tauri builder; the WebView navigates to
+/// `handai://localhost/...` for both the static export and the API.
+pub const SCHEME: &str = "handai";
+
+/// Directory (relative to the app's bundled resources) holding the Next.js
+/// static export (`output: "export"`).
+const STATIC_EXPORT_DIR: &str = "web-dist";
+
+/// Entry point registered via `register_asynchronous_uri_scheme_protocol`.
+/// Hands the request to the async runtime and completes it through
+/// `responder` once the route (or file read) resolves.
+pub fn handle(app: &AppHandle, request: Request<Vec<u8>>, responder: UriSchemeResponder) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let response = dispatch(&app, request).await;
+        responder.respond(response);
+    });
+}
+
+async fn dispatch(app: &AppHandle, request: Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> {
+    let path = request.uri().path().to_string();
+
+    if let Some(api_path) = path.strip_prefix("/api/") {
+        return crate::api::handle(app, api_path, &request).await;
+    }
+
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    serve_static(app, &path, range).await
+}
+
+/// Confirms the static export is present *and* that `/api/health` actually
+/// answers `200`, rather than just checking that `index.html` exists on
+/// disk. Used by the startup supervisor (`supervisor.rs`) to detect a
+/// missing/half-written bundle or a broken API router before the WebView
+/// ever navigates to `handai://localhost` — the in-process analogue of
+/// polling a readiness endpoint instead of trusting a bare TCP connect.
+pub async fn probe(app: &AppHandle) -> Result<(), String> {
+    let root = app
+        .path()
+        .resolve(STATIC_EXPORT_DIR, tauri::path::BaseDirectory::Resource)
+        .map_err(|e| format!("static export dir unavailable: {e}"))?;
+    if !root.join("index.html").exists() {
+        return Err(format!("missing {}/index.html", root.display()));
+    }
+
+    let health_request = Request::builder()
+        .method("GET")
+        .uri(format!("{SCHEME}://localhost/api/health"))
+        .body(Vec::new())
+        .map_err(|e| format!("failed to build health probe request: {e}"))?;
+    let response = crate::api::handle(app, "health", &health_request).await;
+    if response.status() == StatusCode::OK {
+        Ok(())
+    } else {
+        Err(format!(
+            "/api/health returned {}",
+            response.status().as_u16()
+        ))
+    }
+}
+
+/// Serves a file out of the bundled static export, answering full or
+/// range requests. Falls back to `index.html` for extensionless paths so
+/// client-side routes (e.g. `/dashboard`) resolve like a static-hosted SPA.
+///
+/// Resolution and the actual read happen on a blocking task — this runs on
+/// the same async runtime the protocol handler dispatches through, and an
+/// inline `std::fs::read` here would be exactly the main-thread-blocking
+/// hazard the module doc warns `register_asynchronous_uri_scheme_protocol`
+/// exists to avoid. A `Range` request also only reads the bytes it asked
+/// for, via seek, rather than loading the whole file to then slice it.
+async fn serve_static(app: &AppHandle, path: &str, range: Option<String>) -> Response<Cow<'static, [u8]>> {
+    let root = match app
+        .path()
+        .resolve(STATIC_EXPORT_DIR, tauri::path::BaseDirectory::Resource)
+    {
+        Ok(dir) => dir,
+        Err(e) => return internal_error(format!("static export dir unavailable: {e}")),
+    };
+    let rel = path.trim_start_matches('/').to_string();
+
+    let result = tauri::async_runtime::spawn_blocking(move || read_static_file(&root, &rel, range))
+        .await;
+
+    match result {
+        Ok(Ok((body, mime))) => {
+            let mut builder = Response::builder()
+                .status(body.status)
+                .header(header::CONTENT_TYPE, mime)
+                .header(header::ACCEPT_RANGES, "bytes")
+                .header(header::CONTENT_LENGTH, body.bytes.len().to_string());
+            if let Some(content_range) = body.content_range {
+                builder = builder.header(header::CONTENT_RANGE, content_range);
+            }
+            builder.body(Cow::Owned(body.bytes)).unwrap()
+        }
+        Ok(Err(message)) => not_found(message),
+        Err(join_error) => internal_error(format!("static file task panicked: {join_error}")),
+    }
+}
+
+struct StaticBody {
+    bytes: Vec<u8>,
+    status: StatusCode,
+    content_range: Option<String>,
+}
+
+/// Resolves `rel` to a file under `root` (falling back to `index.html` for
+/// SPA routes) and reads it, honoring `range` if present. Runs inside
+/// [`serve_static`]'s blocking task, so every call here is a plain
+/// synchronous filesystem operation.
+fn read_static_file(
+    root: &Path,
+    rel: &str,
+    range: Option<String>,
+) -> Result<(StaticBody, &'static str), String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file_path = safe_join(root, rel);
+    if file_path.is_dir() || rel.is_empty() {
+        file_path = file_path.join("index.html");
+    }
+    if !file_path.exists() {
+        // A missing path with an extension is a missing asset, not a route.
+        if Path::new(rel).extension().is_some() {
+            return Err(format!("{}: not found", file_path.display()));
+        }
+        // Extensionless client-side route: fall back to the route's own
+        // pre-rendered page, then to the app shell.
+        let html_candidate = safe_join(root, rel).with_extension("html");
+        file_path = if html_candidate.exists() {
+            html_candidate
+        } else {
+            root.join("index.html")
+        };
+    }
+
+    let mime = mime_for(&file_path);
+    let mut file = std::fs::File::open(&file_path)
+        .map_err(|e| format!("{}: {e}", file_path.display()))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("{}: {e}", file_path.display()))?
+        .len() as usize;
+
+    let body = match range.and_then(|r| parse_range(&r, len)) {
+        Some((start, end)) => {
+            file.seek(SeekFrom::Start(start as u64))
+                .map_err(|e| format!("{}: {e}", file_path.display()))?;
+            let mut bytes = vec![0u8; end - start + 1];
+            file.read_exact(&mut bytes)
+                .map_err(|e| format!("{}: {e}", file_path.display()))?;
+            StaticBody {
+                bytes,
+                status: StatusCode::PARTIAL_CONTENT,
+                content_range: Some(format!("bytes {start}-{end}/{len}")),
+            }
+        }
+        None => {
+            let mut bytes = Vec::with_capacity(len);
+            file.read_to_end(&mut bytes)
+                .map_err(|e| format!("{}: {e}", file_path.display()))?;
+            StaticBody {
+                bytes,
+                status: StatusCode::OK,
+                content_range: None,
+            }
+        }
+    };
+
+    Ok((body, mime))
+}
+
+/// Joins `rel` onto `root` while stripping any `..`/root components, so a
+/// crafted request path can't escape the static export directory.
+fn safe_join(root: &Path, rel: &str) -> PathBuf {
+    let mut out = root.to_path_buf();
+    for component in Path::new(rel).components() {
+        if let std::path::Component::Normal(part) = component {
+            out.push(part);
+        }
+    }
+    out
+}
+
+/// Parses a single-range `Range: bytes=start-end` header into an inclusive
+/// `(start, end)` byte pair, clamped to `len`. Multi-range requests and
+/// anything malformed fall back to serving the full body.
+fn parse_range(header: &str, len: usize) -> Option<(usize, usize)> {
+    let spec = header.strip_prefix("bytes=")?;
+    let last = len.checked_sub(1)?;
+
+    // `bytes=-N` is a suffix range ("last N bytes"), not `start-end` — it
+    // has no start half to split on, so it has to be handled before the
+    // general split below or `split_once('-')` reads the `N` as `end_s`
+    // with an empty `start_s`, which serves bytes `0..=N` instead.
+    if let Some(suffix_len) = spec.strip_prefix('-') {
+        let n: usize = suffix_len.parse().ok()?;
+        if n == 0 {
+            return None;
+        }
+        let start = len.saturating_sub(n);
+        return Some((start, last));
+    }
+
+    let (start_s, end_s) = spec.split_once('-')?;
+    let start: usize = start_s.parse().ok()?;
+    let end: usize = if end_s.is_empty() {
+        last
+    } else {
+        end_s.parse::<usize>().ok()?.min(last)
+    };
+    if start > end {
+        return None;
+    }
+    Some((start, end))
+}
+
+fn mime_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("ico") => "image/x-icon",
+        Some("woff2") => "font/woff2",
+        Some("woff") => "font/woff",
+        _ => "application/octet-stream",
+    }
+}
+
+fn not_found(message: String) -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(Cow::Owned(message.into_bytes()))
+        .unwrap()
+}
+
+fn internal_error(message: String) -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+        .status(StatusCode::INTERNAL_SERVER_ERROR)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(Cow::Owned(message.into_bytes()))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_bounded_range() {
+        assert_eq!(parse_range("bytes=0-9", 100), Some((0, 9)));
+    }
+
+    #[test]
+    fn parses_an_open_ended_range() {
+        assert_eq!(parse_range("bytes=90-", 100), Some((90, 99)));
+    }
+
+    #[test]
+    fn parses_a_suffix_range() {
+        assert_eq!(parse_range("bytes=-10", 100), Some((90, 99)));
+    }
+
+    #[test]
+    fn suffix_range_longer_than_the_file_clamps_to_the_start() {
+        assert_eq!(parse_range("bytes=-1000", 100), Some((0, 99)));
+    }
+
+    #[test]
+    fn rejects_a_range_entirely_past_the_end() {
+        assert_eq!(parse_range("bytes=200-300", 100), None);
+    }
+
+    #[test]
+    fn rejects_a_malformed_header() {
+        assert_eq!(parse_range("not-a-range", 100), None);
+    }
+
+    #[test]
+    fn safe_join_strips_parent_and_root_components() {
+        let joined = safe_join(Path::new("/web-dist"), "/../../etc/passwd");
+        assert_eq!(joined, PathBuf::from("/web-dist/etc/passwd"));
+    }
+
+    #[test]
+    fn safe_join_keeps_ordinary_nested_paths() {
+        let joined = safe_join(Path::new("/web-dist"), "assets/app.js");
+        assert_eq!(joined, PathBuf::from("/web-dist/assets/app.js"));
+    }
+}
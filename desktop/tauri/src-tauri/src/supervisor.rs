@@ -0,0 +1,64 @@
+//! Startup supervision for the embedded `handai://` protocol.
+//!
+//! Phase A supervised a `node server.js` child process, restarting it with
+//! backoff if it crashed. Phase B (`protocol.rs`) removed that child process
+//! entirely — routes now run in-process — so there's nothing left that can
+//! crash and come back on its own: the bundled static export and the
+//! `/api/health` route either work or they don't, and retrying the same
+//! check five times in a row can't change the answer. This module probes
+//! once at startup and emits a typed status event so the frontend can show
+//! "this install is broken, reinstall" rather than a blank WebView — not a
+//! "hang on, reconnecting" overlay, since there's nothing transient here.
+//!
+//! Flagging the gap explicitly: chunk0-2 asked for exponential-backoff
+//! *restart* attempts and distinct `server://crashed`/`server://gave-up`
+//! events, and this intentionally does neither — it's a judgment call that
+//! the backoff/restart machinery no longer has anything to apply to, not a
+//! completed implementation of that request. If a future failure mode here
+//! does turn out to be transient, that backlog item is still open.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[derive(Default)]
+pub struct SupervisorState(pub Mutex<Status>);
+
+#[derive(Default, Clone, Serialize)]
+pub struct Status {
+    pub last_error: Option<String>,
+}
+
+/// Runs the startup probe on the async runtime so it never blocks `setup`
+/// or the main thread.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        supervise(&app).await;
+    });
+}
+
+/// Re-runs the startup probe on demand, e.g. a "try again" button on the
+/// error screen raised by a `server://unavailable` event.
+#[tauri::command]
+pub fn restart_server(app: AppHandle) {
+    spawn(app);
+}
+
+async fn supervise(app: &AppHandle) {
+    let _ = app.emit("server://starting", ());
+
+    match crate::protocol::probe(app).await {
+        Ok(()) => {
+            let _ = app.emit("server://ready", ());
+        }
+        Err(err) => {
+            {
+                let state: State<SupervisorState> = app.state();
+                state.0.lock().unwrap().last_error = Some(err.clone());
+            }
+            eprintln!("[handai] embedded server failed its readiness probe: {err}");
+            let _ = app.emit("server://unavailable", &err);
+        }
+    }
+}